@@ -0,0 +1,499 @@
+use std::collections::HashMap;
+use std::io::{BufReader, Read, Write};
+use std::ops::AddAssign;
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+use anyhow::Context;
+use clap::ValueEnum;
+use ignore::WalkBuilder;
+use serde::Serialize;
+
+/// File contents information for a single file
+#[derive(Debug, Default, Clone, Copy, Serialize, PartialEq, Eq)]
+pub struct FileStats {
+    /// Total number of lines
+    pub lines: u64,
+
+    /// Total number of actual lines of code
+    pub code: u64,
+
+    /// Total number of commented lines
+    pub comments: u64,
+
+    /// Total number of blank lines
+    pub blanks: u64,
+}
+
+/// Information about a specific language
+#[derive(Debug, Default, Serialize)]
+pub struct LanguageStats {
+    /// Accumulated statistics across all files
+    pub stats: FileStats,
+
+    /// Number of files for this language
+    pub file_count: u64,
+}
+
+/// Output format for the aggregated report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "lowercase")]
+pub enum OutputFormat {
+    Table,
+    Json,
+    Yaml,
+    Cbor,
+}
+
+/// Top-level serializable report: per-language stats plus grand totals
+#[derive(Debug, Serialize)]
+pub struct Report<'a> {
+    pub languages: &'a HashMap<String, LanguageStats>,
+    pub totals: LanguageStats,
+}
+
+/// Field to sort the per-language table by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "lowercase")]
+pub enum SortField {
+    Lines,
+    Code,
+    Comments,
+    Files,
+    Name,
+}
+
+/// Describes how to recognize and classify lines for a single language
+#[derive(Debug)]
+pub struct LanguageDef {
+    /// Human-readable name, used as the key in aggregated stats
+    pub name: &'static str,
+
+    /// File extensions (without the leading dot) that map to this language
+    pub extensions: &'static [&'static str],
+
+    /// Tokens that start a line comment, e.g. "//" or "#"
+    pub line_comments: &'static [&'static str],
+
+    /// (start, end) delimiter pairs for block comments, e.g. ("/*", "*/")
+    pub block_comments: &'static [(&'static str, &'static str)],
+}
+
+/// Registry of known languages, keyed by file extension
+#[derive(Debug)]
+pub struct ExtensionRegistry {
+    defs: Vec<LanguageDef>,
+
+    /// Maps a file extension to an index into `defs`
+    by_extension: HashMap<&'static str, usize>,
+}
+
+/// Manages all statistics for the program
+#[derive(Debug)]
+pub struct StatisticsManager {
+    /// Language-specific statistics
+    pub language_stats: HashMap<String, LanguageStats>,
+
+    /// Known languages and how to recognize them
+    pub registry: ExtensionRegistry,
+
+    /// Per-file stats, keyed by language; only populated when `--files` is set
+    files: HashMap<String, Vec<(PathBuf, FileStats)>>,
+}
+
+impl FileStats {
+    fn add(&mut self, stats: FileStats) {
+        self.blanks += stats.blanks;
+        self.comments += stats.comments;
+        self.lines += stats.lines;
+        self.code += stats.code;
+    }
+}
+
+impl LanguageStats {
+    fn add(&mut self, stats: FileStats) {
+        self.stats.add(stats);
+        self.file_count += 1;
+    }
+}
+
+impl AddAssign<&FileStats> for FileStats {
+    fn add_assign(&mut self, other: &FileStats) {
+        self.lines += other.lines;
+        self.code += other.code;
+        self.comments += other.comments;
+        self.blanks += other.blanks;
+    }
+}
+
+impl AddAssign<&LanguageStats> for LanguageStats {
+    fn add_assign(&mut self, other: &LanguageStats) {
+        self.stats += &other.stats;
+        self.file_count += other.file_count;
+    }
+}
+
+impl ExtensionRegistry {
+    pub fn new() -> Self {
+        let defs = vec![
+            LanguageDef {
+                name: "Rust",
+                extensions: &["rs"],
+                line_comments: &["//"],
+                block_comments: &[("/*", "*/")],
+            },
+            LanguageDef {
+                name: "Python",
+                extensions: &["py"],
+                line_comments: &["#"],
+                block_comments: &[],
+            },
+            LanguageDef {
+                name: "C/C++",
+                extensions: &["c", "h", "cpp", "hpp", "cc", "cxx"],
+                line_comments: &["//"],
+                block_comments: &[("/*", "*/")],
+            },
+            LanguageDef {
+                name: "JavaScript",
+                extensions: &["js", "jsx", "ts", "tsx"],
+                line_comments: &["//"],
+                block_comments: &[("/*", "*/")],
+            },
+            LanguageDef {
+                name: "Markdown",
+                extensions: &["md"],
+                line_comments: &[],
+                block_comments: &[("<!--", "-->")],
+            },
+        ];
+
+        let mut by_extension = HashMap::new();
+        for (i, def) in defs.iter().enumerate() {
+            for ext in def.extensions {
+                by_extension.insert(*ext, i);
+            }
+        }
+
+        Self { defs, by_extension }
+    }
+
+    pub fn lookup(&self, ext: &str) -> Option<&LanguageDef> {
+        self.by_extension.get(ext).map(|&i| &self.defs[i])
+    }
+
+    pub fn language_names(&self) -> Vec<&'static str> {
+        self.defs.iter().map(|def| def.name).collect()
+    }
+}
+
+impl Default for ExtensionRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Default for StatisticsManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StatisticsManager {
+    pub fn new() -> Self {
+        Self {
+            language_stats: HashMap::new(),
+            registry: ExtensionRegistry::new(),
+            files: HashMap::new(),
+        }
+    }
+
+    /// Folds a single file's results into the aggregated statistics
+    pub fn merge(&mut self, language: String, stats: FileStats) {
+        self.language_stats.entry(language).or_default().add(stats);
+    }
+
+    /// Records a single file's stats for later per-file listing
+    pub fn record_file(&mut self, language: String, path: PathBuf, stats: FileStats) {
+        self.files.entry(language).or_default().push((path, stats));
+    }
+
+    pub fn print_statistics(&self, sort: SortField, show_files: bool) {
+        let mut languages: Vec<(&String, &LanguageStats)> = self.language_stats.iter().collect();
+
+        languages.sort_by(|(name_a, a), (name_b, b)| match sort {
+            SortField::Lines => b.stats.lines.cmp(&a.stats.lines),
+            SortField::Code => b.stats.code.cmp(&a.stats.code),
+            SortField::Comments => b.stats.comments.cmp(&a.stats.comments),
+            SortField::Files => b.file_count.cmp(&a.file_count),
+            SortField::Name => name_a.cmp(name_b),
+        });
+
+        println!("LANGUAGE  FILES    CODE    COMMENTS   BLANKS  TOTAL LINES");
+        println!("{}", "*".repeat(58));
+
+        for (lang, stats) in &languages {
+            println!(
+                "{lang}:   {}  {}  {}  {}   {}",
+                stats.file_count,
+                stats.stats.code,
+                stats.stats.comments,
+                stats.stats.blanks,
+                stats.stats.lines
+            );
+
+            if show_files {
+                if let Some(files) = self.files.get(*lang) {
+                    for (path, stats) in files {
+                        println!(
+                            "    {}: {} {} {} {}",
+                            path.display(),
+                            stats.code,
+                            stats.comments,
+                            stats.blanks,
+                            stats.lines
+                        );
+                    }
+                }
+            }
+        }
+
+        let total = self.total();
+        println!("{}", "*".repeat(58));
+        println!(
+            "TOTAL:   {}  {}  {}  {}   {}",
+            total.file_count,
+            total.stats.code,
+            total.stats.comments,
+            total.stats.blanks,
+            total.stats.lines
+        );
+    }
+
+    /// Folds every language's stats into a single grand-total row
+    pub fn total(&self) -> LanguageStats {
+        let mut total = LanguageStats::default();
+        for stats in self.language_stats.values() {
+            total += stats;
+        }
+        total
+    }
+
+    pub fn total_files(&self) -> u64 {
+        self.total().file_count
+    }
+
+    pub fn report(&self) -> Report<'_> {
+        Report {
+            languages: &self.language_stats,
+            totals: self.total(),
+        }
+    }
+
+    /// Prints the aggregated statistics in the requested `format`
+    pub fn print(
+        &self,
+        format: OutputFormat,
+        sort: SortField,
+        show_files: bool,
+    ) -> anyhow::Result<()> {
+        match format {
+            OutputFormat::Table => self.print_statistics(sort, show_files),
+            OutputFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(&self.report())?);
+            }
+            OutputFormat::Yaml => {
+                print!("{}", serde_yaml::to_string(&self.report())?);
+            }
+            OutputFormat::Cbor => {
+                let bytes = serde_cbor::to_vec(&self.report())?;
+                io::stdout().write_all(&bytes)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub fn read_file_content(filepath: &Path) -> anyhow::Result<String> {
+    let file = fs::OpenOptions::new()
+        .read(true)
+        .open(filepath)
+        .context(format!("open file :{:?}", filepath))?;
+
+    let mut reader = BufReader::new(file);
+    let mut content = String::new();
+
+    reader
+        .read_to_string(&mut content)
+        .context("read file content")?;
+    Ok(content)
+}
+
+/// Recursively collects every file under `dir_path`, honoring `.gitignore`
+/// / `.ignore` rules and hidden-file skipping unless told otherwise.
+pub fn read_dir_recursively(
+    dir_path: &Path,
+    include_hidden: bool,
+    respect_gitignore: bool,
+) -> anyhow::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+
+    let walker = WalkBuilder::new(dir_path)
+        .hidden(!include_hidden)
+        .git_ignore(respect_gitignore)
+        .ignore(respect_gitignore)
+        .git_exclude(respect_gitignore)
+        .build();
+
+    for entry in walker {
+        let entry = entry.context(format!("walk dir: {:?}", dir_path))?;
+        if entry.file_type().is_some_and(|ft| ft.is_file()) {
+            files.push(entry.into_path());
+        }
+    }
+
+    Ok(files)
+}
+
+/// Reads up to the first few KB of `filepath` and reports whether it looks
+/// like a binary file (i.e. contains a NUL byte), so we don't try to
+/// `read_to_string` it and fail the whole run.
+pub fn looks_binary(filepath: &Path) -> anyhow::Result<bool> {
+    const PEEK_SIZE: usize = 8192;
+
+    let file = fs::OpenOptions::new()
+        .read(true)
+        .open(filepath)
+        .context(format!("open file :{:?}", filepath))?;
+
+    let mut reader = BufReader::new(file);
+    let mut buf = vec![0u8; PEEK_SIZE];
+    let n = reader.read(&mut buf).context("peek file content")?;
+
+    Ok(buf[..n].contains(&0))
+}
+
+/// Returns true if `path` should be counted, given `--include`/`--exclude`
+/// glob patterns. An excluded path is always dropped; when `include` is
+/// non-empty, only paths matching at least one include pattern survive.
+pub fn passes_filters(path: &Path, include: &[glob::Pattern], exclude: &[glob::Pattern]) -> bool {
+    let path_str = path.to_string_lossy();
+
+    if exclude.iter().any(|pat| pat.matches(&path_str)) {
+        return false;
+    }
+
+    include.is_empty() || include.iter().any(|pat| pat.matches(&path_str))
+}
+
+/// Reads and classifies a single file. Returns `None` when the file's
+/// extension is not recognized, it looks binary, or it could not be read,
+/// so one bad file does not abort the whole run.
+pub fn analyze(filepath: &Path, registry: &ExtensionRegistry) -> Option<(String, FileStats)> {
+    let ext = filepath.extension().and_then(|e| e.to_str())?;
+    let def = registry.lookup(ext)?;
+
+    match looks_binary(filepath) {
+        Ok(true) => return None,
+        Ok(false) => {}
+        Err(err) => {
+            eprintln!("skipping {filepath:?}: {err:#}");
+            return None;
+        }
+    }
+
+    match process_file_with_def(filepath, def) {
+        Ok(stats) => Some((def.name.to_string(), stats)),
+        Err(err) => {
+            eprintln!("skipping {filepath:?}: {err:#}");
+            None
+        }
+    }
+}
+
+/// Classifies every line of `filepath` according to `def` and accumulates
+/// the resulting `FileStats`.
+pub fn process_file_with_def(filepath: &Path, def: &LanguageDef) -> anyhow::Result<FileStats> {
+    let content = read_file_content(filepath)?;
+
+    let mut stats = FileStats::default();
+    let mut block_depth: usize = 0;
+
+    for line in content.lines() {
+        stats.lines += 1;
+
+        if block_depth > 0 {
+            stats.comments += 1;
+            update_block_depth(line, def, &mut block_depth);
+            continue;
+        }
+
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() {
+            stats.blanks += 1;
+            continue;
+        }
+
+        if def.line_comments.iter().any(|tok| trimmed.starts_with(tok)) {
+            stats.comments += 1;
+            continue;
+        }
+
+        stats.code += 1;
+        update_block_depth(line, def, &mut block_depth);
+    }
+
+    Ok(stats)
+}
+
+/// Scans `line` for `def`'s block-comment delimiters, ignoring any that
+/// appear inside string literals, and adjusts `block_depth` accordingly.
+fn update_block_depth(line: &str, def: &LanguageDef, block_depth: &mut usize) {
+    if def.block_comments.is_empty() {
+        return;
+    }
+
+    let mut chars = line.char_indices();
+    let mut in_string = false;
+
+    while let Some((i, c)) = chars.next() {
+        if *block_depth > 0 {
+            // Already inside a block comment: quote characters are just
+            // comment text here, not string delimiters, so string state
+            // must not leak in. Only look for the end token.
+            let rest = &line[i..];
+            if def
+                .block_comments
+                .iter()
+                .any(|(_, end)| rest.starts_with(end))
+            {
+                *block_depth -= 1;
+            }
+            continue;
+        }
+
+        if in_string {
+            if c == '\\' {
+                chars.next();
+            } else if c == '"' || c == '\'' {
+                in_string = false;
+            }
+            continue;
+        }
+
+        if c == '"' || c == '\'' {
+            in_string = true;
+            continue;
+        }
+
+        let rest = &line[i..];
+        if def
+            .block_comments
+            .iter()
+            .any(|(start, _)| rest.starts_with(start))
+        {
+            *block_depth += 1;
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use std::path::Path;
+
+use count::{process_file_with_def, ExtensionRegistry, FileStats};
+
+fn stats_for(path: &str, ext: &str) -> FileStats {
+    let registry = ExtensionRegistry::new();
+    let def = registry.lookup(ext).expect("known extension");
+    process_file_with_def(Path::new(path), def).expect("fixture should be readable")
+}
+
+#[test]
+fn rust_fixture_counts_lines_correctly() {
+    let stats = stats_for("tests/data/sample.rs", "rs");
+    assert_eq!(
+        stats,
+        FileStats {
+            lines: 13,
+            code: 8,
+            comments: 3,
+            blanks: 2,
+        }
+    );
+}
+
+#[test]
+fn c_fixture_counts_lines_correctly() {
+    let stats = stats_for("tests/data/sample.c", "c");
+    assert_eq!(
+        stats,
+        FileStats {
+            lines: 11,
+            code: 7,
+            comments: 3,
+            blanks: 1,
+        }
+    );
+}
+
+#[test]
+fn markdown_fixture_counts_lines_correctly() {
+    let stats = stats_for("tests/data/sample.md", "md");
+    assert_eq!(
+        stats,
+        FileStats {
+            lines: 9,
+            code: 4,
+            comments: 2,
+            blanks: 3,
+        }
+    );
+}
+
+#[test]
+fn apostrophe_inside_block_comment_does_not_leak_string_state() {
+    let stats = stats_for("tests/data/sample_quote_in_block_comment.rs", "rs");
+    assert_eq!(
+        stats,
+        FileStats {
+            lines: 5,
+            code: 5,
+            comments: 0,
+            blanks: 0,
+        }
+    );
+}
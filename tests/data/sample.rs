@@ -0,0 +1,13 @@
+// top-level comment
+fn main() {
+    let s = "// not a comment";
+    println!("{}", s);
+
+    let url = "http://example.com/*wow*";
+    let y = 2;
+
+    /* this is
+       a block comment
+       spanning lines */
+    let z = 3; /* inline block comment */
+}
@@ -0,0 +1,5 @@
+fn main() {
+    let x = 1; /* it's fine */
+    let y = 2;
+    let z = 3;
+}